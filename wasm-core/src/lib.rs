@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
@@ -43,26 +45,215 @@ pub fn normalize_metric_series(values: JsValue) -> Result<JsValue, JsValue> {
         .map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricSummary {
+    pub normalized: Vec<f64>,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub count: usize,
+    pub trend_slope: f64,
+}
+
+/// Linearly interpolated percentile (`p` in `[0, 1]`) over an already-sorted
+/// slice, using the same convention as common numpy-style "linear" method.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+
+    let rank = p * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    if lower == upper {
+        sorted[lower]
+    } else {
+        let frac = rank - lower as f64;
+        sorted[lower] + (sorted[upper] - sorted[lower]) * frac
+    }
+}
+
+/// Slope of an ordinary-least-squares fit of `values` over the index axis.
+fn trend_slope(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let mean_i = (values.len() - 1) as f64 / 2.0;
+    let mean_v = values.iter().sum::<f64>() / n;
+
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    for (i, value) in values.iter().enumerate() {
+        let di = i as f64 - mean_i;
+        numerator += di * (value - mean_v);
+        denominator += di * di;
+    }
+
+    if denominator <= f64::EPSILON {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
+
+/// Normalizes `values` like `normalize_metric_series`, but folds in the
+/// summary statistics the UI otherwise has to recompute in JS after the
+/// call, avoiding a second pass over the data and keeping the math in one
+/// place.
 #[wasm_bindgen]
-pub fn correlate_metric_series(left: JsValue, right: JsValue) -> Result<f64, JsValue> {
-    let left_values: Vec<f64> = serde_wasm_bindgen::from_value(left)
-        .map_err(|e| JsValue::from_str(&format!("Invalid left input: {e}")))?;
-    let right_values: Vec<f64> = serde_wasm_bindgen::from_value(right)
-        .map_err(|e| JsValue::from_str(&format!("Invalid right input: {e}")))?;
+pub fn summarize_metric_series(values: JsValue) -> Result<JsValue, JsValue> {
+    let input: Vec<f64> = serde_wasm_bindgen::from_value(values)
+        .map_err(|e| JsValue::from_str(&format!("Invalid input: {e}")))?;
 
-    if left_values.is_empty() || right_values.is_empty() || left_values.len() != right_values.len() {
-        return Ok(0.0);
+    if input.is_empty() {
+        let summary = MetricSummary {
+            normalized: Vec::new(),
+            min: 0.0,
+            max: 0.0,
+            mean: 0.0,
+            std: 0.0,
+            p50: 0.0,
+            p95: 0.0,
+            count: 0,
+            trend_slope: 0.0,
+        };
+        return serde_wasm_bindgen::to_value(&summary)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")));
+    }
+
+    let min = input
+        .iter()
+        .fold(f64::INFINITY, |acc, value| acc.min(*value));
+    let max = input
+        .iter()
+        .fold(f64::NEG_INFINITY, |acc, value| acc.max(*value));
+
+    let normalized = if (max - min).abs() < f64::EPSILON {
+        vec![0.0; input.len()]
+    } else {
+        input.iter().map(|value| (value - min) / (max - min)).collect()
+    };
+
+    let n = input.len() as f64;
+    let mean = input.iter().sum::<f64>() / n;
+    let variance = if input.len() > 1 {
+        input.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+    } else {
+        0.0
+    };
+    let std = variance.sqrt();
+
+    let mut sorted = input.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let summary = MetricSummary {
+        normalized,
+        min,
+        max,
+        mean,
+        std,
+        p50: percentile(&sorted, 0.5),
+        p95: percentile(&sorted, 0.95),
+        count: input.len(),
+        trend_slope: trend_slope(&input),
+    };
+
+    serde_wasm_bindgen::to_value(&summary)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricAnomaly {
+    pub index: usize,
+    pub value: f64,
+    pub score: f64,
+    pub is_anomaly: bool,
+}
+
+/// Median of a slice of values. Not defined for empty input.
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Scores each point using the median-absolute-deviation (MAD) method, which
+/// is resistant to the few extreme values that throw off min/max
+/// normalization. Falls back to a mean/standard-deviation z-score when the
+/// series is too tightly clustered around its median for MAD to be useful,
+/// and falls back again to all-zero scores when that is also degenerate.
+#[wasm_bindgen]
+pub fn detect_metric_anomalies(values: JsValue, threshold: Option<f64>) -> Result<JsValue, JsValue> {
+    let input: Vec<f64> = serde_wasm_bindgen::from_value(values)
+        .map_err(|e| JsValue::from_str(&format!("Invalid input: {e}")))?;
+    let threshold = threshold.unwrap_or(3.5);
+
+    if input.is_empty() {
+        return serde_wasm_bindgen::to_value(&Vec::<MetricAnomaly>::new())
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")));
+    }
+
+    let m = median(&input);
+    let mad = median(&input.iter().map(|x| (x - m).abs()).collect::<Vec<_>>());
+
+    let scores: Vec<f64> = if mad > f64::EPSILON {
+        input.iter().map(|x| 0.6745 * (x - m) / mad).collect()
+    } else {
+        let n = input.len() as f64;
+        let mean = input.iter().sum::<f64>() / n;
+        let variance = if input.len() > 1 {
+            input.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        let std = variance.sqrt();
+
+        if std > f64::EPSILON {
+            input.iter().map(|x| (x - mean) / std).collect()
+        } else {
+            vec![0.0; input.len()]
+        }
+    };
+
+    let anomalies: Vec<MetricAnomaly> = input
+        .iter()
+        .zip(scores.iter())
+        .enumerate()
+        .map(|(index, (value, score))| MetricAnomaly {
+            index,
+            value: *value,
+            score: *score,
+            is_anomaly: score.abs() > threshold,
+        })
+        .collect();
+
+    serde_wasm_bindgen::to_value(&anomalies)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Pearson correlation coefficient over two equal-length slices, clamped to `[-1, 1]`.
+/// Returns `0.0` for empty input or when either series has no variance.
+fn pearson_coefficient(left: &[f64], right: &[f64]) -> f64 {
+    if left.is_empty() || right.is_empty() || left.len() != right.len() {
+        return 0.0;
     }
 
-    let n = left_values.len() as f64;
-    let left_mean = left_values.iter().sum::<f64>() / n;
-    let right_mean = right_values.iter().sum::<f64>() / n;
+    let n = left.len() as f64;
+    let left_mean = left.iter().sum::<f64>() / n;
+    let right_mean = right.iter().sum::<f64>() / n;
 
     let mut numerator = 0.0;
     let mut left_sq = 0.0;
     let mut right_sq = 0.0;
 
-    for (l, r) in left_values.iter().zip(right_values.iter()) {
+    for (l, r) in left.iter().zip(right.iter()) {
         let dl = l - left_mean;
         let dr = r - right_mean;
         numerator += dl * dr;
@@ -71,16 +262,195 @@ pub fn correlate_metric_series(left: JsValue, right: JsValue) -> Result<f64, JsV
     }
 
     if left_sq <= f64::EPSILON || right_sq <= f64::EPSILON {
+        return 0.0;
+    }
+
+    (numerator / (left_sq.sqrt() * right_sq.sqrt())).clamp(-1.0, 1.0)
+}
+
+#[wasm_bindgen]
+pub fn correlate_metric_series(left: JsValue, right: JsValue) -> Result<f64, JsValue> {
+    let left_values: Vec<f64> = serde_wasm_bindgen::from_value(left)
+        .map_err(|e| JsValue::from_str(&format!("Invalid left input: {e}")))?;
+    let right_values: Vec<f64> = serde_wasm_bindgen::from_value(right)
+        .map_err(|e| JsValue::from_str(&format!("Invalid right input: {e}")))?;
+
+    Ok(pearson_coefficient(&left_values, &right_values))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LagCorrelation {
+    pub lag: i32,
+    pub coefficient: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrossCorrelationResult {
+    pub best_lag: i32,
+    pub coefficient: f64,
+    pub per_lag: Vec<LagCorrelation>,
+}
+
+/// Slides `right` against `left` by `k` samples and returns the overlapping
+/// `(left, right)` windows for that lag. Positive `k` means `left` leads:
+/// `left[i]` is paired with `right[i + k]`.
+fn overlap_at_lag<'a>(left: &'a [f64], right: &'a [f64], k: i32) -> (&'a [f64], &'a [f64]) {
+    if k >= 0 {
+        let k = k as usize;
+        if k >= right.len() {
+            return (&left[0..0], &right[0..0]);
+        }
+        let len = left.len().min(right.len() - k);
+        (&left[..len], &right[k..k + len])
+    } else {
+        let k = (-k) as usize;
+        if k >= left.len() {
+            return (&left[0..0], &right[0..0]);
+        }
+        let len = (left.len() - k).min(right.len());
+        (&left[k..k + len], &right[..len])
+    }
+}
+
+/// Sweeps integer lags in `[-max_lag, max_lag]`, shifting `right` relative to
+/// `left` by each lag and computing the Pearson coefficient over the
+/// overlapping region, so callers can tell which series leads the other
+/// (e.g. "CPU saturation leads p99 latency by ~3 intervals").
+#[wasm_bindgen]
+pub fn cross_correlate_metric_series(
+    left: JsValue,
+    right: JsValue,
+    max_lag: u32,
+) -> Result<JsValue, JsValue> {
+    let left_values: Vec<f64> = serde_wasm_bindgen::from_value(left)
+        .map_err(|e| JsValue::from_str(&format!("Invalid left input: {e}")))?;
+    let right_values: Vec<f64> = serde_wasm_bindgen::from_value(right)
+        .map_err(|e| JsValue::from_str(&format!("Invalid right input: {e}")))?;
+
+    let max_lag = max_lag as i32;
+    let mut per_lag = Vec::with_capacity((2 * max_lag + 1) as usize);
+    let mut best: Option<(i32, f64)> = None;
+
+    for lag in -max_lag..=max_lag {
+        let (l_window, r_window) = overlap_at_lag(&left_values, &right_values, lag);
+        let coefficient = if l_window.len() < 2 {
+            0.0
+        } else {
+            pearson_coefficient(l_window, r_window)
+        };
+
+        let is_better = match best {
+            Some((_, best_coefficient)) => coefficient.abs() > best_coefficient.abs(),
+            None => true,
+        };
+        if is_better {
+            best = Some((lag, coefficient));
+        }
+
+        per_lag.push(LagCorrelation { lag, coefficient });
+    }
+
+    let (best_lag, best_coefficient) = best.unwrap_or((-max_lag, 0.0));
+
+    let result = CrossCorrelationResult {
+        best_lag,
+        coefficient: best_coefficient,
+        per_lag,
+    };
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Linearly interpolates `values` onto `target_len` evenly spaced points, so
+/// two series sampled at different step sizes or with scrape gaps can be
+/// brought onto a common length before correlation.
+fn resample(values: &[f64], target_len: usize) -> Vec<f64> {
+    if values.is_empty() || target_len == 0 {
+        return Vec::new();
+    }
+    if values.len() == 1 || target_len == 1 {
+        return vec![values[0]; target_len];
+    }
+
+    let n = values.len();
+    (0..target_len)
+        .map(|j| {
+            let p = j as f64 * (n - 1) as f64 / (target_len - 1) as f64;
+            let lower = p.floor() as usize;
+            let upper = p.ceil() as usize;
+            if lower == upper {
+                values[lower]
+            } else {
+                let frac = p - lower as f64;
+                values[lower] + (values[upper] - values[lower]) * frac
+            }
+        })
+        .collect()
+}
+
+/// Resamples `values` onto `target_len` evenly spaced points via linear
+/// interpolation, for aligning metric series sampled at different step
+/// sizes before correlating them.
+#[wasm_bindgen]
+pub fn resample_metric_series(values: JsValue, target_len: usize) -> Result<JsValue, JsValue> {
+    let input: Vec<f64> = serde_wasm_bindgen::from_value(values)
+        .map_err(|e| JsValue::from_str(&format!("Invalid input: {e}")))?;
+
+    serde_wasm_bindgen::to_value(&resample(&input, target_len))
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Like `correlate_metric_series`, but first resamples the shorter series up
+/// to the longer series' length so mismatched step sizes and scrape gaps
+/// don't collapse the result to `0.0`.
+#[wasm_bindgen]
+pub fn correlate_metric_series_aligned(left: JsValue, right: JsValue) -> Result<f64, JsValue> {
+    let left_values: Vec<f64> = serde_wasm_bindgen::from_value(left)
+        .map_err(|e| JsValue::from_str(&format!("Invalid left input: {e}")))?;
+    let right_values: Vec<f64> = serde_wasm_bindgen::from_value(right)
+        .map_err(|e| JsValue::from_str(&format!("Invalid right input: {e}")))?;
+
+    if left_values.is_empty() || right_values.is_empty() {
         return Ok(0.0);
     }
 
-    Ok((numerator / (left_sq.sqrt() * right_sq.sqrt())).clamp(-1.0, 1.0))
+    let target_len = left_values.len().max(right_values.len());
+    let left_aligned = resample(&left_values, target_len);
+    let right_aligned = resample(&right_values, target_len);
+
+    Ok(pearson_coefficient(&left_aligned, &right_aligned))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceRule {
+    pub kind: String,
+    pub match_substrings: Vec<String>,
+    pub output: String,
+}
+
+thread_local! {
+    static STATUS_RULES: RefCell<Vec<ResourceRule>> = const { RefCell::new(Vec::new()) };
 }
 
+/// Replaces the caller-configurable status rules evaluated by
+/// `shape_resource_status` before it falls back to the built-in classifier.
+/// Rules are evaluated in the order given, per `kind`, so callers can tune
+/// status classification for a cluster's CRDs and conventions without
+/// recompiling the WASM module.
 #[wasm_bindgen]
-pub fn shape_resource_status(kind: String, status: String) -> String {
-    let normalized = status.to_lowercase();
+pub fn configure_status_rules(rules: JsValue) -> Result<(), JsValue> {
+    let parsed: Vec<ResourceRule> = serde_wasm_bindgen::from_value(rules)
+        .map_err(|e| JsValue::from_str(&format!("Invalid rules: {e}")))?;
+
+    STATUS_RULES.with(|cell| *cell.borrow_mut() = parsed);
+
+    Ok(())
+}
 
+/// Built-in status classification, used when no configured rule matches the
+/// given `kind`/`status` pair.
+fn shape_resource_status_builtin(kind: &str, normalized: &str) -> String {
     if kind.eq_ignore_ascii_case("pod") {
         if normalized.contains("running") {
             return "running".into();
@@ -94,6 +464,70 @@ pub fn shape_resource_status(kind: String, status: String) -> String {
         return "warning".into();
     }
 
+    if kind.eq_ignore_ascii_case("job") {
+        if normalized.contains("complete") || normalized.contains("succeeded") {
+            return "succeeded".into();
+        }
+        if normalized.contains("failed") || normalized.contains("error") {
+            return "error".into();
+        }
+        if normalized.contains("pending") {
+            return "pending".into();
+        }
+        if normalized.contains("running") || normalized.contains("active") {
+            return "running".into();
+        }
+        return "warning".into();
+    }
+
+    if kind.eq_ignore_ascii_case("deployment") || kind.eq_ignore_ascii_case("statefulset") {
+        if normalized.contains("degraded") || normalized.contains("unavailable") {
+            return "degraded".into();
+        }
+        if normalized.contains("error") || normalized.contains("crash") {
+            return "error".into();
+        }
+        if normalized.contains("progressing") || normalized.contains("pending") {
+            return "pending".into();
+        }
+        if normalized.contains("available") || normalized.contains("ready") || normalized.contains("running") {
+            return "running".into();
+        }
+        return "warning".into();
+    }
+
+    if kind.eq_ignore_ascii_case("persistentvolumeclaim") || kind.eq_ignore_ascii_case("pvc") {
+        if normalized.contains("bound") {
+            return "running".into();
+        }
+        if normalized.contains("pending") {
+            return "pending".into();
+        }
+        if normalized.contains("lost") || normalized.contains("failed") || normalized.contains("error") {
+            return "error".into();
+        }
+        return "warning".into();
+    }
+
+    if kind.eq_ignore_ascii_case("node") {
+        if normalized.contains("notready") || normalized.contains("error") {
+            return "error".into();
+        }
+        if normalized.contains("pending") {
+            return "pending".into();
+        }
+        if normalized.contains("ready") {
+            return "running".into();
+        }
+        return "warning".into();
+    }
+
+    if normalized.contains("succeeded") || normalized.contains("complete") {
+        return "succeeded".into();
+    }
+    if normalized.contains("degraded") {
+        return "degraded".into();
+    }
     if normalized.contains("error") || normalized.contains("crash") {
         return "error".into();
     }
@@ -106,3 +540,27 @@ pub fn shape_resource_status(kind: String, status: String) -> String {
 
     "warning".into()
 }
+
+#[wasm_bindgen]
+pub fn shape_resource_status(kind: String, status: String) -> String {
+    let normalized = status.to_lowercase();
+
+    let configured = STATUS_RULES.with(|cell| {
+        cell.borrow()
+            .iter()
+            .find(|rule| {
+                rule.kind.eq_ignore_ascii_case(&kind)
+                    && rule
+                        .match_substrings
+                        .iter()
+                        .any(|substring| normalized.contains(&substring.to_lowercase()))
+            })
+            .map(|rule| rule.output.clone())
+    });
+
+    if let Some(output) = configured {
+        return output;
+    }
+
+    shape_resource_status_builtin(&kind, &normalized)
+}